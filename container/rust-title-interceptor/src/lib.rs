@@ -1,10 +1,12 @@
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::os::unix::io::RawFd;
+use std::os::unix::net::{UnixDatagram, UnixStream};
 use std::sync::Mutex;
-use libc::{c_void, size_t, ssize_t, dlsym, RTLD_NEXT};
+use libc::{c_int, c_void, iovec, off_t, size_t, ssize_t, dlsym, RTLD_NEXT};
 use lazy_static::lazy_static;
 use chrono::Local;
+use serde::Serialize;
 use std::env;
 use std::sync::Once;
 
@@ -13,43 +15,244 @@ const STDERR_FILENO: RawFd = 2;
 const TITLE_LOG_FILE: &str = "/tmp/catnip_syscall_titles.log";
 
 type WriteFn = unsafe extern "C" fn(RawFd, *const c_void, size_t) -> ssize_t;
+type WritevFn = unsafe extern "C" fn(RawFd, *const iovec, c_int) -> ssize_t;
+type PwriteFn = unsafe extern "C" fn(RawFd, *const c_void, size_t, off_t) -> ssize_t;
+type PwritevFn = unsafe extern "C" fn(RawFd, *const iovec, c_int, off_t) -> ssize_t;
+type CloseFn = unsafe extern "C" fn(RawFd) -> c_int;
 
 lazy_static! {
-    static ref LOG_MUTEX: Mutex<()> = Mutex::new(());
+    static ref LOG_MUTEX: Mutex<SinkCache> = Mutex::new(SinkCache::default());
     static ref ORIGINAL_WRITE: WriteFn = unsafe { init_original_write() };
+    static ref ORIGINAL_WRITEV: WritevFn = unsafe { init_original_writev() };
+    static ref ORIGINAL_PWRITE: PwriteFn = unsafe { init_original_pwrite() };
+    static ref ORIGINAL_PWRITEV: PwritevFn = unsafe { init_original_pwritev() };
+    static ref ORIGINAL_CLOSE: CloseFn = unsafe { init_original_close() };
+    // Read once and cached: every hooked write in every LD_PRELOADed process
+    // pays this check, so it must never cost more than a bool read once
+    // interception is found to be disabled.
+    static ref TITLE_INTERCEPT_ENABLED: bool =
+        matches!(env::var("CATNIP_TITLE_INTERCEPT").as_deref(), Ok("1"));
 }
 
 static INIT: Once = Once::new();
 
 unsafe fn init_original_write() -> WriteFn {
-    let write_ptr = dlsym(RTLD_NEXT, b"write\0".as_ptr() as *const libc::c_char);
+    let write_ptr = dlsym(RTLD_NEXT, c"write".as_ptr());
     if write_ptr.is_null() {
         panic!("Failed to get original write function");
     }
     std::mem::transmute(write_ptr)
 }
 
+unsafe fn init_original_writev() -> WritevFn {
+    let writev_ptr = dlsym(RTLD_NEXT, c"writev".as_ptr());
+    if writev_ptr.is_null() {
+        panic!("Failed to get original writev function");
+    }
+    std::mem::transmute(writev_ptr)
+}
+
+unsafe fn init_original_pwrite() -> PwriteFn {
+    let pwrite_ptr = dlsym(RTLD_NEXT, c"pwrite".as_ptr());
+    if pwrite_ptr.is_null() {
+        panic!("Failed to get original pwrite function");
+    }
+    std::mem::transmute(pwrite_ptr)
+}
+
+unsafe fn init_original_pwritev() -> PwritevFn {
+    let pwritev_ptr = dlsym(RTLD_NEXT, c"pwritev".as_ptr());
+    if pwritev_ptr.is_null() {
+        panic!("Failed to get original pwritev function");
+    }
+    std::mem::transmute(pwritev_ptr)
+}
+
+unsafe fn init_original_close() -> CloseFn {
+    let close_ptr = dlsym(RTLD_NEXT, c"close".as_ptr());
+    if close_ptr.is_null() {
+        panic!("Failed to get original close function");
+    }
+    std::mem::transmute(close_ptr)
+}
+
+/// # Safety
+///
+/// Same contract as libc's `write`: `buf` must be valid for reads of
+/// `count` bytes for the duration of the call.
 #[no_mangle]
 pub unsafe extern "C" fn write(fd: RawFd, buf: *const c_void, count: size_t) -> ssize_t {
     // Ensure we have the original function
     let original = *ORIGINAL_WRITE;
-    
+
     // Call the original write function first
     let result = original(fd, buf, count);
-    
-    // Only scan stdout and stderr for title sequences
-    if result > 0 && (fd == STDOUT_FILENO || fd == STDERR_FILENO) && !buf.is_null() && count > 0 {
-        if let Ok(enabled) = env::var("CATNIP_TITLE_INTERCEPT") {
-            if enabled == "1" {
-                let data = std::slice::from_raw_parts(buf as *const u8, result as usize);
-                scan_for_title_sequences(data);
-            }
-        }
+    let saved_errno = *libc::__errno_location();
+
+    // Bail out before is_target_fd/guarded_scan (a TTY_CACHE lock plus,
+    // on first sight of a fd, an isatty syscall) so a disabled shim adds
+    // nothing to the host's hot path beyond this one bool read.
+    if result > 0 && *TITLE_INTERCEPT_ENABLED && is_target_fd(fd) && !buf.is_null() && count > 0 {
+        guarded_scan(|| {
+            let data = std::slice::from_raw_parts(buf as *const u8, result as usize);
+            scan_for_title_sequences(fd, data);
+        });
+    }
+
+    *libc::__errno_location() = saved_errno;
+    result
+}
+
+/// # Safety
+///
+/// Same contract as libc's `writev`: `iov` must point to `iovcnt` valid
+/// `iovec` entries, each readable for its `iov_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn writev(fd: RawFd, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+    let original = *ORIGINAL_WRITEV;
+
+    let result = original(fd, iov, iovcnt);
+    let saved_errno = *libc::__errno_location();
+
+    if result > 0 && *TITLE_INTERCEPT_ENABLED && is_target_fd(fd) && !iov.is_null() && iovcnt > 0 {
+        guarded_scan(|| {
+            let data = collect_iovec_bytes(iov, iovcnt, result as usize);
+            scan_for_title_sequences(fd, &data);
+        });
     }
-    
+
+    *libc::__errno_location() = saved_errno;
     result
 }
 
+/// # Safety
+///
+/// Same contract as libc's `pwrite`: `buf` must be valid for reads of
+/// `count` bytes for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn pwrite(fd: RawFd, buf: *const c_void, count: size_t, offset: off_t) -> ssize_t {
+    let original = *ORIGINAL_PWRITE;
+
+    let result = original(fd, buf, count, offset);
+    let saved_errno = *libc::__errno_location();
+
+    if result > 0 && *TITLE_INTERCEPT_ENABLED && is_target_fd(fd) && !buf.is_null() && count > 0 {
+        guarded_scan(|| {
+            let data = std::slice::from_raw_parts(buf as *const u8, result as usize);
+            scan_for_title_sequences(fd, data);
+        });
+    }
+
+    *libc::__errno_location() = saved_errno;
+    result
+}
+
+/// # Safety
+///
+/// Same contract as libc's `pwritev`: `iov` must point to `iovcnt` valid
+/// `iovec` entries, each readable for its `iov_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pwritev(fd: RawFd, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t {
+    let original = *ORIGINAL_PWRITEV;
+
+    let result = original(fd, iov, iovcnt, offset);
+    let saved_errno = *libc::__errno_location();
+
+    if result > 0 && *TITLE_INTERCEPT_ENABLED && is_target_fd(fd) && !iov.is_null() && iovcnt > 0 {
+        guarded_scan(|| {
+            let data = collect_iovec_bytes(iov, iovcnt, result as usize);
+            scan_for_title_sequences(fd, &data);
+        });
+    }
+
+    *libc::__errno_location() = saved_errno;
+    result
+}
+
+/// # Safety
+///
+/// Same contract as libc's `close`: `fd` must be a file descriptor valid to
+/// close (the usual close-after-close caveats around fd reuse apply).
+#[no_mangle]
+pub unsafe extern "C" fn close(fd: RawFd) -> c_int {
+    let original = *ORIGINAL_CLOSE;
+
+    let result = original(fd);
+    let saved_errno = *libc::__errno_location();
+
+    // The kernel is free to hand this fd number to an unrelated file on the
+    // very next open()/socket()/etc, so any cached TTY verdict or in-flight
+    // OSC parser state for it must not survive past a successful close —
+    // otherwise a later reuse inherits a stale decision or stale bytes that
+    // belong to a completely different stream.
+    if result == 0 {
+        TTY_CACHE.lock().unwrap_or_else(|e| e.into_inner()).remove(&fd);
+        PARSER_STATES.lock().unwrap_or_else(|e| e.into_inner()).remove(&fd);
+    }
+
+    *libc::__errno_location() = saved_errno;
+    result
+}
+
+thread_local! {
+    // Guards against recursing back into our own hooks if the sink write
+    // (e.g. a Unix socket connect/send) is itself satisfied by a hooked
+    // function.
+    static SCANNING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+struct ScanGuard;
+
+impl Drop for ScanGuard {
+    fn drop(&mut self) {
+        SCANNING.with(|flag| flag.set(false));
+    }
+}
+
+fn enter_scan() -> Option<ScanGuard> {
+    SCANNING.with(|flag| {
+        if flag.get() {
+            None
+        } else {
+            flag.set(true);
+            Some(ScanGuard)
+        }
+    })
+}
+
+// Runs the scan/log closure behind the reentrancy guard and a panic
+// boundary: this is an LD_PRELOAD shim, so a panic or infinite recursion in
+// our own code must never take down the host process.
+fn guarded_scan<F: FnOnce()>(scan: F) {
+    if let Some(_guard) = enter_scan() {
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(scan));
+    }
+}
+
+// Reconstruct the logical byte stream written by a vectored call by walking
+// the iovec array up to the number of bytes the kernel actually accepted,
+// since a title sequence can straddle two iovec entries.
+unsafe fn collect_iovec_bytes(iov: *const iovec, iovcnt: c_int, written: usize) -> Vec<u8> {
+    let iovecs = std::slice::from_raw_parts(iov, iovcnt as usize);
+    let mut data = Vec::with_capacity(written.min(4096));
+    let mut remaining = written;
+
+    for entry in iovecs {
+        if remaining == 0 {
+            break;
+        }
+        let take = entry.iov_len.min(remaining);
+        if take == 0 {
+            continue;
+        }
+        let bytes = std::slice::from_raw_parts(entry.iov_base as *const u8, take);
+        data.extend_from_slice(bytes);
+        remaining -= take;
+    }
+
+    data
+}
+
 fn get_current_working_directory() -> String {
     match env::current_dir() {
         Ok(path) => path.to_string_lossy().to_string(),
@@ -63,81 +266,395 @@ fn get_current_working_directory() -> String {
     }
 }
 
-fn scan_for_title_sequences(data: &[u8]) {
-    let mut i = 0;
-    while i < data.len().saturating_sub(4) {
-        // Look for OSC sequences: \x1b]0; or \x1b]2;
-        if data[i] == 0x1b && data[i + 1] == b']' && 
-           (data[i + 2] == b'0' || data[i + 2] == b'2') && 
-           data[i + 3] == b';' {
-            
-            // Found start of title sequence
-            let title_start = i + 4;
-            let mut title_end = title_start;
-            
-            // Find the terminator (\x07 or \x1b\\)
-            while title_end < data.len() {
-                if data[title_end] == 0x07 {
-                    // Bell terminator found
-                    break;
-                } else if title_end < data.len() - 1 && 
-                          data[title_end] == 0x1b && 
-                          data[title_end + 1] == b'\\' {
-                    // ESC backslash terminator found
-                    break;
+// Per-fd OSC parser state, so a sequence split across two write() calls
+// (common with line-buffered or small-chunk output) is reassembled instead
+// of silently dropped. The OSC code (0/2 for titles, 7 for cwd, 133 for
+// shell-integration markers) is variable-width, so it's accumulated as
+// digits before the body is collected.
+#[derive(Default)]
+enum OscParserState {
+    #[default]
+    Normal,
+    SawEsc,
+    // Saw ESC ], waiting for the first digit of the OSC code.
+    SawBracket,
+    // Collecting the ASCII digits of the OSC code, waiting for ';'.
+    CollectingCode(Vec<u8>),
+    InBody { code: Vec<u8>, buf: Vec<u8> },
+    // Body saw an ESC; waiting to see whether '\' follows to confirm a
+    // String Terminator. The ESC may be the last byte of one buffer and the
+    // '\' the first byte of the next, so this must survive across calls.
+    PendingTerminator { code: Vec<u8>, buf: Vec<u8> },
+}
+
+const MAX_TITLE_LEN: usize = 200;
+const MAX_OSC_CODE_LEN: usize = 4;
+const MAX_OSC_BODY_LEN: usize = 4096;
+
+// The title codes (0/2) are capped at a short display-friendly length, but
+// other bodies (an OSC 7 cwd, in particular) can legitimately be much
+// longer — e.g. a deep monorepo/node_modules path — so only title bodies
+// get the tight cap. Other codes still get a generous bound so a
+// never-terminated sequence can't grow the buffer unbounded.
+fn max_body_len(code: &[u8]) -> usize {
+    match code {
+        b"0" | b"2" => MAX_TITLE_LEN,
+        _ => MAX_OSC_BODY_LEN,
+    }
+}
+
+lazy_static! {
+    // Keyed by raw fd number, which the kernel recycles once a fd is closed:
+    // both maps are purged for a fd in the close() hook above so a later
+    // reuse never inherits another stream's cached TTY verdict or in-flight
+    // OSC parser state.
+    static ref PARSER_STATES: Mutex<std::collections::HashMap<RawFd, OscParserState>> =
+        Mutex::new(std::collections::HashMap::new());
+    static ref TTY_CACHE: Mutex<std::collections::HashMap<RawFd, bool>> =
+        Mutex::new(std::collections::HashMap::new());
+}
+
+// stdout/stderr are always scanned; other fds are scanned only if they turn
+// out to be a TTY (e.g. a PTY master/slave a coding-agent host spawns a
+// child under), since that's where OSC 7/133 shell-integration sequences
+// actually show up. isatty() results are cached per fd to avoid a syscall on
+// every write.
+fn is_target_fd(fd: RawFd) -> bool {
+    if fd == STDOUT_FILENO || fd == STDERR_FILENO {
+        return true;
+    }
+    let mut cache = TTY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    *cache
+        .entry(fd)
+        .or_insert_with(|| unsafe { libc::isatty(fd) == 1 })
+}
+
+// A completed OSC sequence: its numeric code and body, ready to dispatch.
+type OscEvent = (Vec<u8>, Vec<u8>);
+
+fn scan_for_title_sequences(fd: RawFd, data: &[u8]) {
+    // Collect completed sequences while the per-fd parser state is locked,
+    // then dispatch them only after releasing that lock. Dispatching inline
+    // would run sink I/O (log_event -> LOG_MUTEX, possibly a socket send)
+    // while still holding PARSER_STATES, stalling scanning on every other fd
+    // for as long as the sink call takes.
+    let mut emitted: Vec<OscEvent> = Vec::new();
+
+    {
+        let mut states = PARSER_STATES.lock().unwrap_or_else(|e| e.into_inner());
+        let mut state = states.remove(&fd).unwrap_or_default();
+
+        for &byte in data {
+            let (next_state, event) = osc_step(state, byte);
+            state = next_state;
+            if let Some(event) = event {
+                emitted.push(event);
+            }
+        }
+
+        states.insert(fd, state);
+    }
+
+    for (code, body) in &emitted {
+        dispatch_osc_event(code, body);
+    }
+}
+
+fn osc_step(state: OscParserState, byte: u8) -> (OscParserState, Option<OscEvent>) {
+    match state {
+        OscParserState::Normal => {
+            let next = if byte == 0x1b {
+                OscParserState::SawEsc
+            } else {
+                OscParserState::Normal
+            };
+            (next, None)
+        }
+        OscParserState::SawEsc => {
+            let next = if byte == b']' {
+                OscParserState::SawBracket
+            } else {
+                OscParserState::Normal
+            };
+            (next, None)
+        }
+        OscParserState::SawBracket => {
+            let next = if byte.is_ascii_digit() {
+                OscParserState::CollectingCode(vec![byte])
+            } else {
+                OscParserState::Normal
+            };
+            (next, None)
+        }
+        OscParserState::CollectingCode(mut code) => {
+            let next = if byte == b';' {
+                OscParserState::InBody {
+                    code,
+                    buf: Vec::new(),
                 }
-                title_end += 1;
+            } else if byte.is_ascii_digit() && code.len() < MAX_OSC_CODE_LEN {
+                code.push(byte);
+                OscParserState::CollectingCode(code)
+            } else {
+                OscParserState::Normal
+            };
+            (next, None)
+        }
+        OscParserState::InBody { code, mut buf } => match byte {
+            0x07 => (OscParserState::Normal, Some((code, buf))),
+            0x1b => (OscParserState::PendingTerminator { code, buf }, None),
+            _ => {
+                if buf.len() < max_body_len(&code) {
+                    buf.push(byte);
+                }
+                (OscParserState::InBody { code, buf }, None)
             }
-            
-            // Extract and log the title if we found a complete sequence
-            if title_end < data.len() && title_end > title_start {
-                let title_slice = &data[title_start..title_end];
-                
-                // Limit title length for safety
-                let title_len = title_slice.len().min(200);
-                let title_slice = &title_slice[..title_len];
-                
-                // Validate title is valid UTF-8 and not empty
-                if !title_slice.is_empty() {
-                    if let Ok(title) = std::str::from_utf8(title_slice) {
-                        // Additional validation: ensure it's not just whitespace
-                        if !title.trim().is_empty() {
-                            log_title(title);
-                        }
-                    }
+        },
+        OscParserState::PendingTerminator { code, mut buf } => {
+            if byte == b'\\' {
+                (OscParserState::Normal, Some((code, buf)))
+            } else {
+                // Not actually a String Terminator: the ESC we buffered was
+                // just part of the body, so put it back and reprocess this
+                // byte as ordinary body content.
+                if buf.len() < max_body_len(&code) {
+                    buf.push(0x1b);
                 }
+                osc_step(OscParserState::InBody { code, buf }, byte)
             }
-            
-            // Move past the processed sequence
-            i = title_end;
         }
-        i += 1;
+    }
+}
+
+fn dispatch_osc_event(code: &[u8], body: &[u8]) {
+    match code {
+        b"0" | b"2" => emit_title(body),
+        b"7" => emit_cwd_change(body),
+        b"133" => emit_command_boundary(body),
+        _ => {}
+    }
+}
+
+fn emit_title(title_bytes: &[u8]) {
+    if let Ok(title) = std::str::from_utf8(title_bytes) {
+        if !title.trim().is_empty() {
+            log_title(title);
+        }
+    }
+}
+
+// OSC 7 reports the shell's cwd as `file://<host>/<path>`; we only care
+// about the path, since the host is typically the PTY's own hostname.
+fn emit_cwd_change(body: &[u8]) {
+    let Ok(body) = std::str::from_utf8(body) else {
+        return;
+    };
+    let Some(rest) = body.strip_prefix("file://") else {
+        return;
+    };
+    let Some(slash) = rest.find('/') else {
+        return;
+    };
+    log_event(CatnipEvent::CwdChange {
+        reported_cwd: rest[slash..].to_string(),
+    });
+}
+
+// OSC 133 shell-integration markers delimit a command's lifecycle: A marks a
+// fresh prompt, B marks the start of the user's typed command, C marks the
+// transition into the command's output (the command is now executing), and D
+// marks the command finishing. Only D ends the command; C opens the
+// executing/output phase that D closes.
+fn emit_command_boundary(body: &[u8]) {
+    match body.first() {
+        Some(b'A') => log_event(CatnipEvent::PromptStart),
+        Some(b'B') => log_event(CatnipEvent::CommandStart),
+        Some(b'C') => log_event(CatnipEvent::OutputStart),
+        Some(b'D') => log_event(CatnipEvent::CommandEnd),
+        _ => {}
+    }
+}
+
+// A typed event sent to the sink. `title` carries the classic window-title
+// updates; the rest give a supervisor a structured view of PTY/shell
+// activity beyond the window title.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum CatnipEvent {
+    Title { title: String },
+    CwdChange { reported_cwd: String },
+    PromptStart,
+    CommandStart,
+    OutputStart,
+    CommandEnd,
+}
+
+// The full record written to the sink: the common envelope fields plus the
+// event-specific payload flattened in.
+#[derive(Serialize)]
+struct TitleEvent {
+    timestamp: String,
+    pid: u32,
+    cwd: String,
+    #[serde(flatten)]
+    event: CatnipEvent,
+}
+
+// Where title events go. Selected by CATNIP_TITLE_SINK (`unix:<path>` or
+// `file:<path>`), defaulting to the legacy file sink when unset.
+enum SinkConfig {
+    File(String),
+    Unix(String),
+}
+
+fn parse_sink_config() -> SinkConfig {
+    match env::var("CATNIP_TITLE_SINK") {
+        Ok(spec) => {
+            if let Some(path) = spec.strip_prefix("unix:") {
+                SinkConfig::Unix(path.to_string())
+            } else if let Some(path) = spec.strip_prefix("file:") {
+                SinkConfig::File(path.to_string())
+            } else {
+                SinkConfig::File(TITLE_LOG_FILE.to_string())
+            }
+        }
+        Err(_) => SinkConfig::File(TITLE_LOG_FILE.to_string()),
+    }
+}
+
+enum UnixSinkConnection {
+    Stream(UnixStream),
+    Datagram(UnixDatagram),
+}
+
+enum SendOutcome {
+    Sent,
+    WouldBlock,
+    Failed,
+}
+
+impl UnixSinkConnection {
+    // The socket is put in non-blocking mode in connect_unix_sink, so a
+    // consumer that isn't reading can never stall the write() this runs
+    // inside of: a full buffer comes back as WouldBlock instead of blocking
+    // the host program.
+    fn send(&mut self, bytes: &[u8]) -> SendOutcome {
+        let result = match self {
+            UnixSinkConnection::Stream(stream) => stream.write(bytes).map(|_| ()),
+            UnixSinkConnection::Datagram(dgram) => dgram.send(bytes).map(|_| ()),
+        };
+        match result {
+            Ok(()) => SendOutcome::Sent,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => SendOutcome::WouldBlock,
+            Err(_) => SendOutcome::Failed,
+        }
+    }
+}
+
+fn connect_unix_sink(path: &str) -> Option<UnixSinkConnection> {
+    if let Ok(stream) = UnixStream::connect(path) {
+        stream.set_nonblocking(true).ok()?;
+        return Some(UnixSinkConnection::Stream(stream));
+    }
+    let dgram = UnixDatagram::unbound().ok()?;
+    dgram.connect(path).ok()?;
+    dgram.set_nonblocking(true).ok()?;
+    Some(UnixSinkConnection::Datagram(dgram))
+}
+
+// Cache of the lazily-established sink state, guarded by LOG_MUTEX so a
+// connection is only ever touched from one thread at a time.
+#[derive(Default)]
+struct SinkCache {
+    unix_conn: Option<UnixSinkConnection>,
+}
+
+fn write_to_file(path: &str, bytes: &[u8]) {
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = file.write_all(bytes);
     }
 }
 
 fn log_title(title: &str) {
-    let _guard = LOG_MUTEX.lock().unwrap();
-    
+    log_event(CatnipEvent::Title {
+        title: title.to_string(),
+    });
+}
+
+fn log_event(event: CatnipEvent) {
+    let mut cache = LOG_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+
     let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     let pid = std::process::id();
     let cwd = get_current_working_directory();
-    
-    let log_entry = format!("{}|{}|{}|{}\n", timestamp, pid, cwd, title);
-    
-    // Append to log file
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(TITLE_LOG_FILE) {
-        let _ = file.write_all(log_entry.as_bytes());
+
+    // The legacy pipe format predates structured events and only knows how
+    // to represent a title; other event kinds are always emitted as JSON.
+    let use_legacy_format = matches!(
+        env::var("CATNIP_TITLE_FORMAT").as_deref(),
+        Ok("legacy")
+    ) && matches!(event, CatnipEvent::Title { .. });
+
+    let log_entry = if use_legacy_format {
+        let title = match &event {
+            CatnipEvent::Title { title } => title,
+            _ => unreachable!(),
+        };
+        format!("{}|{}|{}|{}\n", timestamp, pid, cwd, title)
+    } else {
+        let record = TitleEvent {
+            timestamp,
+            pid,
+            cwd,
+            event,
+        };
+        match serde_json::to_string(&record) {
+            Ok(json) => format!("{}\n", json),
+            Err(_) => return,
+        }
+    };
+
+    match parse_sink_config() {
+        SinkConfig::File(path) => write_to_file(&path, log_entry.as_bytes()),
+        SinkConfig::Unix(path) => {
+            if cache.unix_conn.is_none() {
+                cache.unix_conn = connect_unix_sink(&path);
+            }
+
+            match cache
+                .unix_conn
+                .as_mut()
+                .map(|conn| conn.send(log_entry.as_bytes()))
+            {
+                Some(SendOutcome::Sent) => {}
+                Some(SendOutcome::WouldBlock) => {
+                    // The consumer isn't keeping up; drop this one event to
+                    // the file sink rather than blocking the host program.
+                    // The connection itself is kept for the next event.
+                    write_to_file(TITLE_LOG_FILE, log_entry.as_bytes());
+                }
+                Some(SendOutcome::Failed) | None => {
+                    // Either the socket isn't available yet or it dropped out
+                    // from under us; drop the stale connection and fall back
+                    // to the file sink so the host process is never
+                    // disrupted.
+                    cache.unix_conn = None;
+                    write_to_file(TITLE_LOG_FILE, log_entry.as_bytes());
+                }
+            }
+        }
     }
 }
 
 // Constructor function - runs when library is loaded
 #[ctor::ctor]
 fn init() {
-    // Initialize the original write function pointer
+    // Initialize the original function pointers
     INIT.call_once(|| {
         let _ = *ORIGINAL_WRITE;
+        let _ = *ORIGINAL_WRITEV;
+        let _ = *ORIGINAL_PWRITE;
+        let _ = *ORIGINAL_PWRITEV;
+        let _ = *ORIGINAL_CLOSE;
     });
 }
\ No newline at end of file